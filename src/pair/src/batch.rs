@@ -0,0 +1,32 @@
+use soroban_sdk::{contracttype, Address, Bytes};
+
+/// One step of a `Pair::batch` call. Mirrors the arguments of the
+/// corresponding single-call entrypoint.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum Operation {
+    Swap {
+        amount_0_out: i128,
+        amount_1_out: i128,
+        to: Address,
+        sender: Address,
+        data: Bytes,
+    },
+    Mint {
+        sender: Address,
+        to: Address,
+    },
+    Burn {
+        sender: Address,
+        to: Address,
+    },
+}
+
+/// The result of one `Operation`, in the same order as the batch's calls.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub enum OperationResult {
+    Swap,
+    Mint(i128),
+    Burn(i128, i128),
+}
@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Address, Bytes, Env};
+
+/// Implemented by flash-swap borrowers. `swap` invokes this on `to` after
+/// paying out the requested tokens but before checking repayment, letting
+/// the borrower act on funds it doesn't yet hold collateral for.
+#[contractclient(name = "PairCalleeClient")]
+pub trait PairCalleeInterface {
+    fn coralswap_call(env: Env, sender: Address, amount_0: i128, amount_1: i128, data: Bytes);
+}
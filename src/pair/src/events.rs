@@ -0,0 +1,36 @@
+use soroban_sdk::{Address, Env, Symbol};
+
+/// Published whenever a swap settles, after the new reserves are known to
+/// satisfy the K-invariant.
+pub fn swap(
+    env: &Env,
+    sender: &Address,
+    amount_0_in: i128,
+    amount_1_in: i128,
+    amount_0_out: i128,
+    amount_1_out: i128,
+    to: &Address,
+) {
+    let topics = (Symbol::new(env, "swap"), sender.clone(), to.clone());
+    env.events()
+        .publish(topics, (amount_0_in, amount_1_in, amount_0_out, amount_1_out));
+}
+
+/// Published whenever LP shares are minted.
+pub fn mint(env: &Env, sender: &Address, amount_0: i128, amount_1: i128) {
+    let topics = (Symbol::new(env, "mint"), sender.clone());
+    env.events().publish(topics, (amount_0, amount_1));
+}
+
+/// Published whenever LP shares are burned.
+pub fn burn(env: &Env, sender: &Address, amount_0: i128, amount_1: i128, to: &Address) {
+    let topics = (Symbol::new(env, "burn"), sender.clone(), to.clone());
+    env.events().publish(topics, (amount_0, amount_1));
+}
+
+/// Published on every reserve change, so indexers can reconstruct pool
+/// history without polling storage.
+pub fn sync(env: &Env, reserve_0: i128, reserve_1: i128) {
+    let topics = (Symbol::new(env, "sync"),);
+    env.events().publish(topics, (reserve_0, reserve_1));
+}
@@ -1,21 +1,365 @@
 #![no_std]
 
+mod batch;
+mod callback;
 mod errors;
+mod events;
+mod lp_token;
 mod storage;
 
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, token, Address, Bytes, Env, String, Vec, I256};
+use crate::batch::{Operation, OperationResult};
+use crate::callback::PairCalleeClient;
 use crate::errors::PairError;
+use crate::lp_token::LPTokenClient;
 use crate::storage::{DataKey, PairStorage, FeeState, ReentrancyGuard};
 
-fn is_zero_address(env: &Env, address: &Address) -> bool {
+// Fee math is expressed over a 1000-unit denominator (e.g. 3 == 30 bps) so it
+// lines up with `FeeState`'s basis-point fields without floating point.
+const FEE_DENOMINATOR: i128 = 1000;
+
+// Permanently locked in the pair on the first deposit, exactly as Uniswap V2
+// burns it, so a pool can never be fully drained down to zero liquidity.
+const MINIMUM_LIQUIDITY: i128 = 1000;
+
+// Fixed-point scale for the UQ112x112 cumulative-price accumulators.
+const Q112: i128 = 1 << 112;
+
+// Fixed-point scale used for the dynamic-fee mid-price, independent of the
+// TWAP accumulator's Q112 scale since it never needs to survive a shift.
+const PRICE_SCALE: i128 = 1_000_000_000;
+
+// EWMA smoothing factor for the volatility estimate, expressed as a
+// numerator over 100 (alpha = 20%).
+const VOL_ALPHA_NUM: i128 = 20;
+const VOL_ALPHA_DEN: i128 = 100;
+
+// Tunable gain mapping one bps of EWMA volatility to bps of fee widening.
+const FEE_GAIN_K: i128 = 2;
+
+fn zero_address(env: &Env) -> Address {
     // We use a zeroed-out contract ID as the "zero address".
-    // Since from_contract_id is private/unstable in some contexts, 
+    // Since from_contract_id is private/unstable in some contexts,
     // we use a valid but "empty" address representation.
-    let zero_address = Address::from_string(&String::from_str(env, "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"));
-    address == &zero_address
+    Address::from_string(&String::from_str(env, "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"))
+}
+
+fn is_zero_address(env: &Env, address: &Address) -> bool {
+    address == &zero_address(env)
+}
+
+// Integer square root (Babylonian method), used to seed LP supply from
+// `sqrt(amount_0 * amount_1)` on a pool's first deposit.
+fn sqrt(y: i128) -> i128 {
+    if y > 3 {
+        let mut z = y;
+        let mut x = y / 2 + 1;
+        while x < z {
+            z = x;
+            x = (y / x + x) / 2;
+        }
+        z
+    } else if y != 0 {
+        1
+    } else {
+        0
+    }
+}
+
+// Accumulates the TWAP observations for the elapsed time since the last
+// reserve-mutating call, then advances `block_timestamp_last`. Must run
+// before the caller overwrites `storage.reserve_{0,1}` with their new
+// values, since it needs the *old* reserves to price the elapsed window.
+fn update_cumulative_prices(env: &Env, storage: &mut PairStorage) {
+    let now = env.ledger().timestamp();
+    let time_elapsed = now.saturating_sub(storage.block_timestamp_last) as i128;
+
+    if time_elapsed > 0 && storage.reserve_0 > 0 && storage.reserve_1 > 0 {
+        // `reserve * Q112` alone can already exceed i128's range for
+        // realistic reserves (i128::MAX / 2^112 is only ~32768), so this
+        // math runs in I256, which has enough headroom for that
+        // multiplication, the division, and the running sum across the
+        // pair's whole lifetime.
+        let reserve_0 = I256::from_i128(env, storage.reserve_0);
+        let reserve_1 = I256::from_i128(env, storage.reserve_1);
+        let q112 = I256::from_i128(env, Q112);
+        let elapsed = I256::from_i128(env, time_elapsed);
+
+        let price_0 = reserve_1.mul(&q112).div(&reserve_0);
+        let price_1 = reserve_0.mul(&q112).div(&reserve_1);
+
+        storage.price_0_cumulative_last = storage.price_0_cumulative_last.add(&price_0.mul(&elapsed));
+        storage.price_1_cumulative_last = storage.price_1_cumulative_last.add(&price_1.mul(&elapsed));
+    }
+
+    storage.block_timestamp_last = now;
+}
+
+// Updates the EWMA volatility estimate from the post-trade mid-price and
+// derives the effective fee for the swap currently in flight, persisting
+// both so `get_fee_state` reflects it immediately.
+fn update_fee_state(env: &Env, balance_0: i128, balance_1: i128) -> u32 {
+    let mut fee_state: FeeState = env.storage().instance().get(&DataKey::FeeState).unwrap();
+
+    if balance_0 > 0 && balance_1 > 0 {
+        // `balance_1 * PRICE_SCALE` can exceed i128 for large-decimal tokens
+        // and large pools (the same headroom problem `update_cumulative_prices`
+        // widens to I256 for), so this multiplication/division runs in I256.
+        let new_price = I256::from_i128(env, balance_1)
+            .mul(&I256::from_i128(env, PRICE_SCALE))
+            .div(&I256::from_i128(env, balance_0))
+            .to_i128()
+            .unwrap();
+        if fee_state.last_price > 0 {
+            let diff = (new_price - fee_state.last_price).abs();
+            let r_bps = (diff * 10_000) / fee_state.last_price;
+            fee_state.volatility_bps = (VOL_ALPHA_NUM * r_bps
+                + (VOL_ALPHA_DEN - VOL_ALPHA_NUM) * fee_state.volatility_bps)
+                / VOL_ALPHA_DEN;
+        }
+        fee_state.last_price = new_price;
+    }
+
+    let target = fee_state.baseline_bps as i128 + FEE_GAIN_K * fee_state.volatility_bps;
+    fee_state.current_bps = target.clamp(fee_state.min_bps as i128, fee_state.max_bps as i128) as u32;
+
+    env.storage().instance().set(&DataKey::FeeState, &fee_state);
+    fee_state.current_bps
+}
+
+fn acquire_lock(env: &Env) -> Result<(), PairError> {
+    let guard: ReentrancyGuard = env.storage().instance().get(&DataKey::ReentrancyGuard).unwrap();
+    if guard.locked {
+        return Err(PairError::Locked);
+    }
+    env.storage().instance().set(&DataKey::ReentrancyGuard, &ReentrancyGuard { locked: true });
+    Ok(())
+}
+
+fn release_lock(env: &Env) {
+    env.storage().instance().set(&DataKey::ReentrancyGuard, &ReentrancyGuard { locked: false });
+}
+
+fn execute_swap(
+    env: &Env,
+    amount_0_out: i128,
+    amount_1_out: i128,
+    to: &Address,
+    sender: &Address,
+    data: &Bytes,
+) -> Result<(), PairError> {
+    // `sender` is recorded as the `Swap` event's sender topic and forwarded
+    // to the flash-swap callback, so it must be the authenticated caller
+    // rather than caller-supplied attribution.
+    sender.require_auth();
+
+    if amount_0_out < 0 || amount_1_out < 0 {
+        return Err(PairError::InvalidAmount);
+    }
+    // Exactly one side of the swap must be requested.
+    if (amount_0_out > 0) == (amount_1_out > 0) {
+        return Err(PairError::InsufficientOutputAmount);
+    }
+
+    let mut storage: PairStorage = env.storage().instance().get(&DataKey::PairStorage).unwrap();
+    if amount_0_out >= storage.reserve_0 || amount_1_out >= storage.reserve_1 {
+        return Err(PairError::InsufficientLiquidity);
+    }
+
+    let token_0 = token::Client::new(env, &storage.token_0);
+    let token_1 = token::Client::new(env, &storage.token_1);
+    let this = env.current_contract_address();
+
+    // Optimistically pay out the requested side before checking what came in,
+    // exactly as Uniswap V2 does so the caller's transfer can be observed below.
+    if amount_0_out > 0 {
+        token_0.transfer(&this, to, &amount_0_out);
+    }
+    if amount_1_out > 0 {
+        token_1.transfer(&this, to, &amount_1_out);
+    }
+
+    // Flash-swap callback: the borrower already holds the output tokens at
+    // this point and can act on them before repaying, as long as it repays
+    // (or returns the tokens) before we read balances below. The
+    // reentrancy guard acquired by the caller stays held across this
+    // external call, so `to` cannot re-enter swap/mint/burn on this pair.
+    if !data.is_empty() {
+        PairCalleeClient::new(env, to).coralswap_call(sender, &amount_0_out, &amount_1_out, data);
+    }
+
+    let balance_0 = token_0.balance(&this);
+    let balance_1 = token_1.balance(&this);
+
+    let amount_0_in = if balance_0 > storage.reserve_0 - amount_0_out {
+        balance_0 - (storage.reserve_0 - amount_0_out)
+    } else {
+        0
+    };
+    let amount_1_in = if balance_1 > storage.reserve_1 - amount_1_out {
+        balance_1 - (storage.reserve_1 - amount_1_out)
+    } else {
+        0
+    };
+    if amount_0_in == 0 && amount_1_in == 0 {
+        return Err(PairError::InsufficientInputAmount);
+    }
+
+    let fee_bps = update_fee_state(env, balance_0, balance_1) as i128;
+
+    // Every multiplication below can exceed i128 for realistic reserves:
+    // e.g. two 18-decimal-asset reserves of just one whole token each
+    // already overflow `reserve_0 * reserve_1 * FEE_DENOMINATOR^2` in i128
+    // (i128::MAX is ~1.7e38, that product needs ~1e42). Same headroom
+    // problem `update_cumulative_prices` widens to I256 for.
+    let fee_denominator = I256::from_i128(env, FEE_DENOMINATOR);
+    let fee_bps_i256 = I256::from_i128(env, fee_bps);
+    let ten = I256::from_i128(env, 10);
+    // balance_i * 1000 - amount_i_in * fee_bps / 10 charges the fee only on
+    // the portion of the balance that was just deposited.
+    let balance_0_adjusted = I256::from_i128(env, balance_0)
+        .mul(&fee_denominator)
+        .sub(&I256::from_i128(env, amount_0_in).mul(&fee_bps_i256).div(&ten));
+    let balance_1_adjusted = I256::from_i128(env, balance_1)
+        .mul(&fee_denominator)
+        .sub(&I256::from_i128(env, amount_1_in).mul(&fee_bps_i256).div(&ten));
+    let k_before = I256::from_i128(env, storage.reserve_0)
+        .mul(&I256::from_i128(env, storage.reserve_1))
+        .mul(&fee_denominator)
+        .mul(&fee_denominator);
+    if balance_0_adjusted.mul(&balance_1_adjusted) < k_before {
+        return Err(PairError::KInvariant);
+    }
+
+    update_cumulative_prices(env, &mut storage);
+    storage.reserve_0 = balance_0;
+    storage.reserve_1 = balance_1;
+    env.storage().instance().set(&DataKey::PairStorage, &storage);
+
+    events::swap(env, sender, amount_0_in, amount_1_in, amount_0_out, amount_1_out, to);
+    events::sync(env, storage.reserve_0, storage.reserve_1);
+
+    Ok(())
+}
+
+fn execute_mint(env: &Env, sender: &Address, to: &Address) -> Result<i128, PairError> {
+    // `sender` is recorded as the `Mint` event's sender topic, so it must be
+    // the authenticated caller rather than caller-supplied attribution, the
+    // same as `swap`'s `sender`.
+    sender.require_auth();
+
+    let mut storage: PairStorage = env.storage().instance().get(&DataKey::PairStorage).unwrap();
+    let token_0 = token::Client::new(env, &storage.token_0);
+    let token_1 = token::Client::new(env, &storage.token_1);
+    let this = env.current_contract_address();
+
+    let balance_0 = token_0.balance(&this);
+    let balance_1 = token_1.balance(&this);
+    let amount_0 = balance_0 - storage.reserve_0;
+    let amount_1 = balance_1 - storage.reserve_1;
+
+    let lp_token = LPTokenClient::new(env, &storage.lp_token);
+    let total_supply = lp_token.total_supply();
+
+    let liquidity = if total_supply == 0 {
+        // Lock MINIMUM_LIQUIDITY forever by minting it to the zero address
+        // so the pool can never be fully drained of shares.
+        lp_token.mint(&zero_address(env), &MINIMUM_LIQUIDITY);
+        sqrt(amount_0 * amount_1) - MINIMUM_LIQUIDITY
+    } else {
+        let liquidity_0 = (amount_0 * total_supply) / storage.reserve_0;
+        let liquidity_1 = (amount_1 * total_supply) / storage.reserve_1;
+        liquidity_0.min(liquidity_1)
+    };
+
+    if liquidity <= 0 {
+        return Err(PairError::InsufficientLiquidityMinted);
+    }
+    lp_token.mint(to, &liquidity);
+
+    update_cumulative_prices(env, &mut storage);
+    storage.reserve_0 = balance_0;
+    storage.reserve_1 = balance_1;
+    env.storage().instance().set(&DataKey::PairStorage, &storage);
+
+    events::mint(env, sender, amount_0, amount_1);
+    events::sync(env, storage.reserve_0, storage.reserve_1);
+
+    Ok(liquidity)
+}
+
+fn execute_burn(env: &Env, sender: &Address, to: &Address) -> Result<(i128, i128), PairError> {
+    // `sender` is recorded as the `Burn` event's sender topic, so it must be
+    // the authenticated caller rather than caller-supplied attribution, the
+    // same as `swap`'s `sender`.
+    sender.require_auth();
+
+    let mut storage: PairStorage = env.storage().instance().get(&DataKey::PairStorage).unwrap();
+    let token_0 = token::Client::new(env, &storage.token_0);
+    let token_1 = token::Client::new(env, &storage.token_1);
+    let lp_token = LPTokenClient::new(env, &storage.lp_token);
+    let this = env.current_contract_address();
+
+    // The caller is expected to have already sent the LP shares to be burned
+    // to this contract, mirroring how `execute_swap` reads transferred-in
+    // token balances rather than taking an explicit amount argument.
+    let liquidity = lp_token.balance(&this);
+    let total_supply = lp_token.total_supply();
+
+    // Must be checked before dividing by `total_supply` below: a pair that
+    // has never had `mint` called has zero shares outstanding.
+    if liquidity <= 0 || total_supply <= 0 {
+        return Err(PairError::InsufficientLiquidityBurned);
+    }
+
+    let balance_0 = token_0.balance(&this);
+    let balance_1 = token_1.balance(&this);
+    let amount_0 = (liquidity * balance_0) / total_supply;
+    let amount_1 = (liquidity * balance_1) / total_supply;
+
+    if amount_0 <= 0 || amount_1 <= 0 {
+        return Err(PairError::InsufficientLiquidityBurned);
+    }
+
+    lp_token.burn(&this, &liquidity);
+    token_0.transfer(&this, to, &amount_0);
+    token_1.transfer(&this, to, &amount_1);
+
+    update_cumulative_prices(env, &mut storage);
+    storage.reserve_0 = token_0.balance(&this);
+    storage.reserve_1 = token_1.balance(&this);
+    env.storage().instance().set(&DataKey::PairStorage, &storage);
+
+    events::burn(env, sender, amount_0, amount_1, to);
+    events::sync(env, storage.reserve_0, storage.reserve_1);
+
+    Ok((amount_0, amount_1))
+}
+
+// Runs every operation in order under the single reentrancy-guard span
+// acquired by the caller. Soroban aborts the whole invocation (and rolls
+// back any storage writes already made by earlier operations) as soon as
+// one returns `Err`, via `?`, so the batch is all-or-nothing.
+fn execute_batch(env: &Env, calls: Vec<Operation>) -> Result<Vec<OperationResult>, PairError> {
+    let mut results = Vec::new(env);
+    for call in calls.iter() {
+        let result = match call {
+            Operation::Swap { amount_0_out, amount_1_out, to, sender, data } => {
+                execute_swap(env, amount_0_out, amount_1_out, &to, &sender, &data)?;
+                OperationResult::Swap
+            }
+            Operation::Mint { sender, to } => OperationResult::Mint(execute_mint(env, &sender, &to)?),
+            Operation::Burn { sender, to } => {
+                let (amount_0, amount_1) = execute_burn(env, &sender, &to)?;
+                OperationResult::Burn(amount_0, amount_1)
+            }
+        };
+        results.push_back(result);
+    }
+    Ok(results)
 }
 
 #[contract]
@@ -67,6 +411,8 @@ impl Pair {
             reserve_0: 0,
             reserve_1: 0,
             block_timestamp_last: 0,
+            price_0_cumulative_last: I256::from_i128(&env, 0),
+            price_1_cumulative_last: I256::from_i128(&env, 0),
         };
         env.storage().instance().set(&DataKey::PairStorage, &storage);
 
@@ -75,6 +421,9 @@ impl Pair {
             baseline_bps: 30, // 30 bps
             min_bps: 10,
             max_bps: 100,
+            last_price: 0,
+            volatility_bps: 0,
+            current_bps: 30,
         };
         env.storage().instance().set(&DataKey::FeeState, &fee_state);
 
@@ -99,4 +448,63 @@ impl Pair {
     pub fn get_fee_state(env: Env) -> FeeState {
         env.storage().instance().get(&DataKey::FeeState).unwrap()
     }
+
+    /// Returns the running UQ112x112 cumulative prices. Differencing two
+    /// observations and dividing by the elapsed time yields a TWAP over that
+    /// window, resistant to single-block price manipulation.
+    pub fn get_price_cumulative(env: Env) -> (I256, I256) {
+        let storage: PairStorage = env.storage().instance().get(&DataKey::PairStorage).unwrap();
+        (storage.price_0_cumulative_last, storage.price_1_cumulative_last)
+    }
+
+    /// `sender` must authorize this call and is forwarded to the
+    /// `coralswap_call` flash-swap callback so the borrower knows who
+    /// initiated the swap; it is also recorded as the `Swap` event's sender
+    /// topic, so callers can't spoof that attribution. Pass an empty `data`
+    /// for a regular swap.
+    pub fn swap(
+        env: Env,
+        amount_0_out: i128,
+        amount_1_out: i128,
+        to: Address,
+        sender: Address,
+        data: Bytes,
+    ) -> Result<(), PairError> {
+        acquire_lock(&env)?;
+        let result = execute_swap(&env, amount_0_out, amount_1_out, &to, &sender, &data);
+        release_lock(&env);
+        result
+    }
+
+    /// Mints LP shares for tokens already transferred into this contract,
+    /// crediting them to `to`. `sender` must authorize this call and is
+    /// recorded as the `mint` event's sender topic, the same as `swap`'s
+    /// `sender`. Returns the amount of liquidity minted.
+    pub fn mint(env: Env, sender: Address, to: Address) -> Result<i128, PairError> {
+        acquire_lock(&env)?;
+        let result = execute_mint(&env, &sender, &to);
+        release_lock(&env);
+        result
+    }
+
+    /// Burns LP shares already transferred into this contract and pays out
+    /// the corresponding share of both reserves to `to`. `sender` must
+    /// authorize this call and is recorded as the `burn` event's sender
+    /// topic, the same as `swap`'s `sender`.
+    pub fn burn(env: Env, sender: Address, to: Address) -> Result<(i128, i128), PairError> {
+        acquire_lock(&env)?;
+        let result = execute_burn(&env, &sender, &to);
+        release_lock(&env);
+        result
+    }
+
+    /// Executes `calls` in order within a single reentrancy-guard span,
+    /// e.g. a deposit immediately followed by a swap. Aborts the whole
+    /// batch (no partial effects) if any step fails.
+    pub fn batch(env: Env, calls: Vec<Operation>) -> Result<Vec<OperationResult>, PairError> {
+        acquire_lock(&env)?;
+        let result = execute_batch(&env, calls);
+        release_lock(&env);
+        result
+    }
 }
@@ -0,0 +1,13 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The dedicated LP-share contract that the factory deploys alongside each
+/// `Pair`. Supply/mint/burn are gated to the pair itself rather than going
+/// through a general-purpose admin, so this interface only exposes what the
+/// pair needs to manage liquidity shares.
+#[contractclient(name = "LPTokenClient")]
+pub trait LPTokenInterface {
+    fn total_supply(env: Env) -> i128;
+    fn balance(env: Env, id: Address) -> i128;
+    fn mint(env: Env, to: Address, amount: i128);
+    fn burn(env: Env, from: Address, amount: i128);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, I256};
 
 #[contracttype]
 #[derive(Clone)]
@@ -18,6 +18,14 @@ pub struct PairStorage {
     pub reserve_0: i128,
     pub reserve_1: i128,
     pub block_timestamp_last: u64,
+    // UQ112x112 fixed-point cumulative prices, each the running sum of
+    // `price * time_elapsed` since the pair was created. Consumers diff two
+    // observations to derive a time-weighted average over the window between
+    // them. Stored as I256: reserves are i128, so `reserve * 2^112` can
+    // already exceed i128's range before it's even divided back down or
+    // accumulated over time, so an i128 accumulator isn't wide enough.
+    pub price_0_cumulative_last: I256,
+    pub price_1_cumulative_last: I256,
 }
 
 #[contracttype]
@@ -26,6 +34,13 @@ pub struct FeeState {
     pub baseline_bps: u32,
     pub min_bps: u32,
     pub max_bps: u32,
+    // EWMA volatility state driving the dynamic fee. `last_price` is the
+    // most recently observed mid-price (reserve_1/reserve_0, fixed-point
+    // scaled), `volatility_bps` is the decaying estimate of relative price
+    // movement, and `current_bps` is the effective fee it last produced.
+    pub last_price: i128,
+    pub volatility_bps: i128,
+    pub current_bps: u32,
 }
 
 #[contracttype]
@@ -1,10 +1,154 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::testutils::{Address as _};
-use soroban_sdk::{Env, Address};
+use soroban_sdk::testutils::{Address as _, Ledger as _};
+use soroban_sdk::{token, Bytes, Env, Address, IntoVal, Symbol, Val, I256};
 use crate::errors::PairError;
 
+/// Standalone LP-share contract satisfying `LPTokenInterface`, used only to
+/// exercise `mint`/`burn` in tests without depending on a real token wasm.
+mod mock_lp_token {
+    use soroban_sdk::{contract, contractimpl, Address, Env, Map, Symbol};
+
+    #[contract]
+    pub struct MockLpToken;
+
+    fn balances(env: &Env) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(env, "bal"))
+            .unwrap_or(Map::new(env))
+    }
+
+    #[contractimpl]
+    impl MockLpToken {
+        pub fn total_supply(env: Env) -> i128 {
+            env.storage().instance().get(&Symbol::new(&env, "supply")).unwrap_or(0)
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            balances(&env).get(id).unwrap_or(0)
+        }
+
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let mut bals = balances(&env);
+            let balance = bals.get(to.clone()).unwrap_or(0) + amount;
+            bals.set(to, balance);
+            env.storage().instance().set(&Symbol::new(&env, "bal"), &bals);
+
+            let supply: i128 = env.storage().instance().get(&Symbol::new(&env, "supply")).unwrap_or(0);
+            env.storage().instance().set(&Symbol::new(&env, "supply"), &(supply + amount));
+        }
+
+        pub fn burn(env: Env, from: Address, amount: i128) {
+            let mut bals = balances(&env);
+            let balance = bals.get(from.clone()).unwrap_or(0) - amount;
+            bals.set(from, balance);
+            env.storage().instance().set(&Symbol::new(&env, "bal"), &bals);
+
+            let supply: i128 = env.storage().instance().get(&Symbol::new(&env, "supply")).unwrap_or(0);
+            env.storage().instance().set(&Symbol::new(&env, "supply"), &(supply - amount));
+        }
+
+        // Not part of `LPTokenInterface` (the pair never needs it), but a
+        // real LP-share token would expose ordinary token transfers too, and
+        // tests need it to move shares into the pair before burning them.
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let mut bals = balances(&env);
+            let from_balance = bals.get(from.clone()).unwrap_or(0) - amount;
+            let to_balance = bals.get(to.clone()).unwrap_or(0) + amount;
+            bals.set(from, from_balance);
+            bals.set(to, to_balance);
+            env.storage().instance().set(&Symbol::new(&env, "bal"), &bals);
+        }
+    }
+}
+
+/// Flash-swap borrower satisfying `PairCalleeInterface`, configurable to
+/// repay a chosen amount of `token` to the pair, for exercising
+/// repay-succeeds and repay-insufficient flash-swap tests.
+mod mock_flash_borrower {
+    use soroban_sdk::{contract, contractimpl, token, Address, Bytes, Env, Symbol};
+
+    #[contract]
+    pub struct MockFlashBorrower;
+
+    #[contractimpl]
+    impl MockFlashBorrower {
+        pub fn configure(env: Env, token: Address, pair: Address, repay_amount: i128) {
+            env.storage().instance().set(&Symbol::new(&env, "token"), &token);
+            env.storage().instance().set(&Symbol::new(&env, "pair"), &pair);
+            env.storage().instance().set(&Symbol::new(&env, "repay"), &repay_amount);
+        }
+
+        pub fn coralswap_call(env: Env, _sender: Address, _amount_0: i128, _amount_1: i128, _data: Bytes) {
+            let token: Address = env.storage().instance().get(&Symbol::new(&env, "token")).unwrap();
+            let pair: Address = env.storage().instance().get(&Symbol::new(&env, "pair")).unwrap();
+            let repay: i128 = env.storage().instance().get(&Symbol::new(&env, "repay")).unwrap();
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &pair, &repay);
+        }
+    }
+}
+
+/// Flash-swap borrower that re-enters the pair during `coralswap_call`, used
+/// to prove the reentrancy guard holds across the callback.
+mod mock_reentrant_callee {
+    use soroban_sdk::{contract, contractimpl, Address, Bytes, Env, Symbol};
+
+    #[contract]
+    pub struct MockReentrantCallee;
+
+    #[contractimpl]
+    impl MockReentrantCallee {
+        pub fn configure(env: Env, pair: Address) {
+            env.storage().instance().set(&Symbol::new(&env, "pair"), &pair);
+        }
+
+        /// True iff the reentrant call was rejected with `PairError::Locked`.
+        pub fn locked_seen(env: Env) -> bool {
+            env.storage().instance().get(&Symbol::new(&env, "locked_seen")).unwrap_or(false)
+        }
+
+        pub fn coralswap_call(env: Env, sender: Address, _amount_0: i128, _amount_1: i128, _data: Bytes) {
+            let pair: Address = env.storage().instance().get(&Symbol::new(&env, "pair")).unwrap();
+            let result = crate::PairClient::new(&env, &pair).try_mint(&sender, &sender);
+            let locked = matches!(result, Err(Ok(crate::errors::PairError::Locked)));
+            env.storage().instance().set(&Symbol::new(&env, "locked_seen"), &locked);
+        }
+    }
+}
+
+fn create_token_contract(env: &Env, admin: &Address) -> Address {
+    env.register_stellar_asset_contract_v2(admin.clone()).address()
+}
+
+/// Returns the data payload of the most recently published event from
+/// `contract_id` whose leading topic is the symbol `name`, if any. Tests
+/// call this right after each action, so "most recent" is "this action's".
+fn event_data(env: &Env, contract_id: &Address, name: &str) -> Option<Val> {
+    let name_val: Val = Symbol::new(env, name).into_val(env);
+    let mut found = None;
+    for (id, topics, data) in env.events().all().iter() {
+        if id == *contract_id && topics.get(0) == Some(name_val) {
+            found = Some(data);
+        }
+    }
+    found
+}
+
+/// Same as `event_data`, but returns the full topic list so tests can assert
+/// on `sender`/`to` rather than just the payload.
+fn event_topics(env: &Env, contract_id: &Address, name: &str) -> Option<soroban_sdk::Vec<Val>> {
+    let name_val: Val = Symbol::new(env, name).into_val(env);
+    let mut found = None;
+    for (id, topics, _data) in env.events().all().iter() {
+        if id == *contract_id && topics.get(0) == Some(name_val) {
+            found = Some(topics);
+        }
+    }
+    found
+}
+
 #[test]
 fn test_initialize_happy_path() {
     let env = Env::default();
@@ -79,3 +223,349 @@ fn test_zero_address_validation() {
     assert_eq!(client.try_initialize(&factory, &token_a, &zero_address, &lp_token), Err(Ok(PairError::ZeroAddress)));
     assert_eq!(client.try_initialize(&factory, &token_a, &token_b, &zero_address), Err(Ok(PairError::ZeroAddress)));
 }
+
+#[test]
+fn test_mint_swap_burn_emit_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(Pair, ());
+    let client = PairClient::new(&env, &contract_id);
+
+    let factory = Address::generate(&env);
+    let admin_0 = Address::generate(&env);
+    let admin_1 = Address::generate(&env);
+    let token_0 = create_token_contract(&env, &admin_0);
+    let token_1 = create_token_contract(&env, &admin_1);
+    let token_0_asset = token::StellarAssetClient::new(&env, &token_0);
+    let token_1_asset = token::StellarAssetClient::new(&env, &token_1);
+    let token_0_client = token::Client::new(&env, &token_0);
+    let token_1_client = token::Client::new(&env, &token_1);
+    let lp_token = env.register(mock_lp_token::MockLpToken, ());
+
+    client.initialize(&factory, &token_0, &token_1, &lp_token);
+
+    // Seed the pool: send both tokens in, then mint LP shares for `lp`.
+    let minter = Address::generate(&env);
+    let lp = Address::generate(&env);
+    token_0_asset.mint(&contract_id, &1_000_000);
+    token_1_asset.mint(&contract_id, &1_000_000);
+    let liquidity = client.mint(&minter, &lp);
+    assert_eq!(liquidity, 1_000_000 - 1000); // sqrt(1e6 * 1e6) - MINIMUM_LIQUIDITY
+
+    assert_eq!(
+        event_data(&env, &contract_id, "mint"),
+        Some((1_000_000i128, 1_000_000i128).into_val(&env)),
+    );
+    // `sender` is `minter`, distinct from the LP-share recipient `lp`.
+    assert_eq!(
+        event_topics(&env, &contract_id, "mint").map(|t| t.get(1).unwrap()),
+        Some(minter.clone().into_val(&env)),
+    );
+    assert_eq!(
+        event_data(&env, &contract_id, "sync"),
+        Some((1_000_000i128, 1_000_000i128).into_val(&env)),
+    );
+
+    // Swap 1_000 of token_0 in for some amount of token_1 out.
+    let trader = Address::generate(&env);
+    token_0_asset.mint(&trader, &1_000);
+    token_0_client.transfer(&trader, &contract_id, &1_000);
+    client.swap(&0, &500, &trader, &trader, &Bytes::new(&env));
+
+    assert_eq!(
+        event_data(&env, &contract_id, "swap"),
+        Some((1_000i128, 0i128, 0i128, 500i128).into_val(&env)),
+    );
+
+    let (reserve_0_after_swap, reserve_1_after_swap, _) = client.get_reserves();
+    assert_eq!(
+        event_data(&env, &contract_id, "sync"),
+        Some((reserve_0_after_swap, reserve_1_after_swap).into_val(&env)),
+    );
+
+    // Send the freshly minted LP shares back to the pair and burn them,
+    // paid out to a third address distinct from both `sender` and `lp`.
+    let burner = Address::generate(&env);
+    let payout_to = Address::generate(&env);
+    let lp_share_client = mock_lp_token::MockLpTokenClient::new(&env, &lp_token);
+    lp_share_client.transfer(&lp, &contract_id, &liquidity);
+    let (amount_0, amount_1) = client.burn(&burner, &payout_to);
+    assert!(amount_0 > 0 && amount_1 > 0);
+
+    assert_eq!(
+        event_data(&env, &contract_id, "burn"),
+        Some((amount_0, amount_1).into_val(&env)),
+    );
+    let burn_topics = event_topics(&env, &contract_id, "burn").unwrap();
+    assert_eq!(burn_topics.get(1).unwrap(), burner.clone().into_val(&env));
+    assert_eq!(burn_topics.get(2).unwrap(), payout_to.clone().into_val(&env));
+
+    let (reserve_0_after_burn, reserve_1_after_burn, _) = client.get_reserves();
+    assert_eq!(
+        event_data(&env, &contract_id, "sync"),
+        Some((reserve_0_after_burn, reserve_1_after_burn).into_val(&env)),
+    );
+}
+
+#[test]
+fn test_price_cumulative_advances_with_elapsed_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(Pair, ());
+    let client = PairClient::new(&env, &contract_id);
+
+    let factory = Address::generate(&env);
+    let admin_0 = Address::generate(&env);
+    let admin_1 = Address::generate(&env);
+    let token_0 = create_token_contract(&env, &admin_0);
+    let token_1 = create_token_contract(&env, &admin_1);
+    let token_0_asset = token::StellarAssetClient::new(&env, &token_0);
+    let token_1_asset = token::StellarAssetClient::new(&env, &token_1);
+    let lp_token = env.register(mock_lp_token::MockLpToken, ());
+
+    client.initialize(&factory, &token_0, &token_1, &lp_token);
+
+    let sender = Address::generate(&env);
+    let lp = Address::generate(&env);
+    token_0_asset.mint(&contract_id, &1_000_000);
+    token_1_asset.mint(&contract_id, &1_000_000);
+    client.mint(&sender, &lp);
+
+    // No time has elapsed since the pair's creation yet, so there is
+    // nothing to accumulate.
+    let (price_0_before, price_1_before) = client.get_price_cumulative();
+    assert_eq!(price_0_before, I256::from_i128(&env, 0));
+    assert_eq!(price_1_before, I256::from_i128(&env, 0));
+
+    // Advance the ledger, then mutate reserves again so `update_cumulative_prices` runs
+    // with a non-zero elapsed time against an equal 1:1 pool.
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let trader = Address::generate(&env);
+    token_0_asset.mint(&trader, &1_000);
+    let token_0_client = token::Client::new(&env, &token_0);
+    token_0_client.transfer(&trader, &contract_id, &1_000);
+    client.swap(&0, &500, &trader, &trader, &Bytes::new(&env));
+
+    let (price_0_after, price_1_after) = client.get_price_cumulative();
+    let q112 = I256::from_i128(&env, 1i128 << 112);
+    let elapsed = I256::from_i128(&env, 1_000);
+    // reserve_1 == reserve_0 at the time `update_cumulative_prices` ran, so both prices are
+    // exactly 1.0 in UQ112x112 terms, times the elapsed window.
+    assert_eq!(price_0_after, q112.mul(&elapsed));
+    assert_eq!(price_1_after, q112.mul(&elapsed));
+}
+
+#[test]
+fn test_burn_without_prior_mint_fails_cleanly() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(Pair, ());
+    let client = PairClient::new(&env, &contract_id);
+
+    let factory = Address::generate(&env);
+    let admin_0 = Address::generate(&env);
+    let admin_1 = Address::generate(&env);
+    let token_0 = create_token_contract(&env, &admin_0);
+    let token_1 = create_token_contract(&env, &admin_1);
+    let lp_token = env.register(mock_lp_token::MockLpToken, ());
+
+    client.initialize(&factory, &token_0, &token_1, &lp_token);
+
+    // No `mint` has ever been called, so `total_supply` is still zero;
+    // `burn` must reject this cleanly instead of dividing by zero.
+    let sender = Address::generate(&env);
+    let to = Address::generate(&env);
+    let result = client.try_burn(&sender, &to);
+    assert_eq!(result, Err(Ok(PairError::InsufficientLiquidityBurned)));
+}
+
+fn setup_flash_swap_pool(env: &Env) -> (Address, PairClient<'_>, Address, Address) {
+    let contract_id = env.register(Pair, ());
+    let client = PairClient::new(env, &contract_id);
+
+    let factory = Address::generate(env);
+    let admin_0 = Address::generate(env);
+    let admin_1 = Address::generate(env);
+    let token_0 = create_token_contract(env, &admin_0);
+    let token_1 = create_token_contract(env, &admin_1);
+    let token_0_asset = token::StellarAssetClient::new(env, &token_0);
+    let token_1_asset = token::StellarAssetClient::new(env, &token_1);
+    let lp_token = env.register(mock_lp_token::MockLpToken, ());
+
+    client.initialize(&factory, &token_0, &token_1, &lp_token);
+
+    let sender = Address::generate(env);
+    let lp = Address::generate(env);
+    token_0_asset.mint(&contract_id, &1_000_000);
+    token_1_asset.mint(&contract_id, &1_000_000);
+    client.mint(&sender, &lp);
+
+    (contract_id, client, token_0, token_1)
+}
+
+#[test]
+fn test_flash_swap_repay_succeeds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, client, _token_0, token_1) = setup_flash_swap_pool(&env);
+
+    let borrower = env.register(mock_flash_borrower::MockFlashBorrower, ());
+    let borrower_client = mock_flash_borrower::MockFlashBorrowerClient::new(&env, &borrower);
+    // The borrower needs more than the 1_000 it flash-borrows on hand to
+    // cover the swap fee when it repays.
+    token::StellarAssetClient::new(&env, &token_1).mint(&borrower, &100);
+    borrower_client.configure(&token_1, &contract_id, &1_005);
+
+    let sender = Address::generate(&env);
+    let result = client.try_swap(&0, &1_000, &borrower, &sender, &Bytes::from_array(&env, &[1]));
+    assert!(result.is_ok());
+
+    let (reserve_0, reserve_1, _) = client.get_reserves();
+    assert_eq!(reserve_0, 1_000_000);
+    assert_eq!(reserve_1, 1_000_000 + 5);
+}
+
+#[test]
+fn test_flash_swap_insufficient_repay_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, client, _token_0, token_1) = setup_flash_swap_pool(&env);
+
+    let borrower = env.register(mock_flash_borrower::MockFlashBorrower, ());
+    let borrower_client = mock_flash_borrower::MockFlashBorrowerClient::new(&env, &borrower);
+    // Repays less than the fee-adjusted amount required to preserve K.
+    borrower_client.configure(&token_1, &contract_id, &500);
+
+    let sender = Address::generate(&env);
+    let result = client.try_swap(&0, &1_000, &borrower, &sender, &Bytes::from_array(&env, &[1]));
+    assert_eq!(result, Err(Ok(PairError::KInvariant)));
+}
+
+#[test]
+fn test_swap_rejects_k_invariant_violation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, client, token_0, _token_1) = setup_flash_swap_pool(&env);
+    let token_0_client = token::Client::new(&env, &token_0);
+    let token_0_asset = token::StellarAssetClient::new(&env, &token_0);
+
+    // Transfer in far too little token_0 to legitimately justify the
+    // requested token_1 output, under a pool with 1_000_000 of both sides.
+    let trader = Address::generate(&env);
+    token_0_asset.mint(&trader, &100);
+    token_0_client.transfer(&trader, &contract_id, &100);
+
+    let result = client.try_swap(&0, &500_000, &trader, &trader, &Bytes::new(&env));
+    assert_eq!(result, Err(Ok(PairError::KInvariant)));
+}
+
+#[test]
+fn test_swap_reentrancy_is_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, client, _token_0, _token_1) = setup_flash_swap_pool(&env);
+
+    let callee = env.register(mock_reentrant_callee::MockReentrantCallee, ());
+    let callee_client = mock_reentrant_callee::MockReentrantCalleeClient::new(&env, &callee);
+    callee_client.configure(&contract_id);
+
+    let sender = Address::generate(&env);
+    let result = client.try_swap(&0, &1_000, &callee, &sender, &Bytes::from_array(&env, &[1]));
+    // The callee's re-entrant `mint` was rejected while the outer swap held
+    // the lock; the outer swap itself still fails because the callee never
+    // repaid the flash-borrowed token_1.
+    assert!(result.is_err());
+    assert!(callee_client.locked_seen());
+}
+
+#[test]
+fn test_fee_widens_with_volatility() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract_id, client, token_0, token_1) = setup_flash_swap_pool(&env);
+    let token_0_client = token::Client::new(&env, &token_0);
+    let token_1_client = token::Client::new(&env, &token_1);
+    let token_0_asset = token::StellarAssetClient::new(&env, &token_0);
+    let token_1_asset = token::StellarAssetClient::new(&env, &token_1);
+
+    let baseline_bps = client.get_fee_state().baseline_bps;
+
+    // First trade: no prior price to compare against, so it only seeds
+    // `last_price` and leaves the fee at baseline.
+    let trader_0 = Address::generate(&env);
+    token_0_asset.mint(&trader_0, &1_000_000);
+    token_0_client.transfer(&trader_0, &contract_id, &1_000_000);
+    client.swap(&0, &1, &trader_0, &trader_0, &Bytes::new(&env));
+    assert_eq!(client.get_fee_state().current_bps, baseline_bps);
+
+    // Second trade swings the price hard in the other direction, so the
+    // EWMA volatility estimate jumps and the effective fee should widen
+    // above baseline.
+    let trader_1 = Address::generate(&env);
+    token_1_asset.mint(&trader_1, &1_000_000);
+    token_1_client.transfer(&trader_1, &contract_id, &1_000_000);
+    client.swap(&1, &0, &trader_1, &trader_1, &Bytes::new(&env));
+
+    let fee_state = client.get_fee_state();
+    assert!(fee_state.volatility_bps > 0);
+    assert!(fee_state.current_bps > baseline_bps);
+}
+
+#[test]
+fn test_batch_rolls_back_entirely_on_failure() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(Pair, ());
+    let client = PairClient::new(&env, &contract_id);
+
+    let factory = Address::generate(&env);
+    let admin_0 = Address::generate(&env);
+    let admin_1 = Address::generate(&env);
+    let token_0 = create_token_contract(&env, &admin_0);
+    let token_1 = create_token_contract(&env, &admin_1);
+    let token_0_asset = token::StellarAssetClient::new(&env, &token_0);
+    let token_1_asset = token::StellarAssetClient::new(&env, &token_1);
+    let lp_token = env.register(mock_lp_token::MockLpToken, ());
+
+    client.initialize(&factory, &token_0, &token_1, &lp_token);
+
+    let sender = Address::generate(&env);
+    let lp = Address::generate(&env);
+    token_0_asset.mint(&contract_id, &1_000_000);
+    token_1_asset.mint(&contract_id, &1_000_000);
+
+    // A valid mint, immediately followed by a swap that can never succeed
+    // (it asks for more token_1 than the pool will hold even after the
+    // mint). The whole batch must fail and leave no trace of the mint.
+    let calls = Vec::from_array(
+        &env,
+        [
+            Operation::Mint { sender: sender.clone(), to: lp.clone() },
+            Operation::Swap {
+                amount_0_out: 0,
+                amount_1_out: 999_999_999,
+                to: sender.clone(),
+                sender: sender.clone(),
+                data: Bytes::new(&env),
+            },
+        ],
+    );
+    let result = client.try_batch(&calls);
+    assert_eq!(result, Err(Ok(PairError::InsufficientLiquidity)));
+
+    let (reserve_0, reserve_1, _) = client.get_reserves();
+    assert_eq!(reserve_0, 0);
+    assert_eq!(reserve_1, 0);
+
+    let lp_client = mock_lp_token::MockLpTokenClient::new(&env, &lp_token);
+    assert_eq!(lp_client.total_supply(), 0);
+}